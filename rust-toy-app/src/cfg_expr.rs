@@ -0,0 +1,513 @@
+//! A small, self-contained parser/evaluator for Rust-style `cfg(...)`
+//! expressions, used to gate per-target build steps declaratively (e.g.
+//! only build a `.deb` on `cfg(target_os = "linux")`, or link a framework on
+//! `cfg(any(target_os = "macos", target_os = "ios"))`).
+//!
+//! Grammar:
+//! ```text
+//! Expr = Ident
+//!      | Ident "=" String
+//!      | "not" "(" Expr ")"
+//!      | "all" "(" Expr ("," Expr)* ","? ")"
+//!      | "any" "(" Expr ("," Expr)* ","? ")"
+//! ```
+
+use std::fmt;
+
+/// A parsed `cfg(...)` expression (the body inside the parens).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgExpr {
+    /// A bare identifier, e.g. `unix`.
+    Ident(String),
+    /// A key/value predicate, e.g. `target_os = "linux"`.
+    KeyValue(String, String),
+    /// `not(expr)`.
+    Not(Box<CfgExpr>),
+    /// `all(expr, ...)`; vacuously true when empty.
+    All(Vec<CfgExpr>),
+    /// `any(expr, ...)`; vacuously false when empty.
+    Any(Vec<CfgExpr>),
+}
+
+/// An error parsing a `cfg(...)` expression, carrying the byte offset of the
+/// offending token so callers can point at the precise span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at byte {})", self.message, self.span)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    String(String),
+    LParen,
+    RParen,
+    Comma,
+    Eq,
+}
+
+struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.rest().chars().next() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, usize)>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let Some(c) = self.rest().chars().next() else {
+            return Ok(None);
+        };
+        match c {
+            '(' => {
+                self.pos += 1;
+                Ok(Some((Token::LParen, start)))
+            }
+            ')' => {
+                self.pos += 1;
+                Ok(Some((Token::RParen, start)))
+            }
+            ',' => {
+                self.pos += 1;
+                Ok(Some((Token::Comma, start)))
+            }
+            '=' => {
+                self.pos += 1;
+                Ok(Some((Token::Eq, start)))
+            }
+            '"' => {
+                self.pos += 1;
+                let value_start = self.pos;
+                loop {
+                    match self.rest().chars().next() {
+                        Some('"') => {
+                            let value = self.input[value_start..self.pos].to_string();
+                            self.pos += 1;
+                            return Ok(Some((Token::String(value), start)));
+                        }
+                        Some(c) => self.pos += c.len_utf8(),
+                        None => {
+                            return Err(ParseError {
+                                message: "unterminated string literal".to_string(),
+                                span: start,
+                            });
+                        }
+                    }
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                while let Some(c) = self.rest().chars().next() {
+                    if c.is_alphanumeric() || c == '_' {
+                        self.pos += c.len_utf8();
+                    } else {
+                        break;
+                    }
+                }
+                Ok(Some((
+                    Token::Ident(self.input[start..self.pos].to_string()),
+                    start,
+                )))
+            }
+            other => Err(ParseError {
+                message: format!("unexpected character {other:?}"),
+                span: start,
+            }),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    end: usize,
+    _input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Self, ParseError> {
+        let mut lexer = Lexer::new(input);
+        let mut tokens = Vec::new();
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+        Ok(Self {
+            tokens,
+            pos: 0,
+            end: input.len(),
+            _input: input,
+        })
+    }
+
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), ParseError> {
+        match self.advance() {
+            Some((token, _)) if &token == expected => Ok(()),
+            Some((token, span)) => Err(ParseError {
+                message: format!("expected {expected:?}, found {token:?}"),
+                span,
+            }),
+            None => Err(ParseError {
+                message: format!("expected {expected:?}, found end of input"),
+                span: self.end,
+            }),
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<CfgExpr, ParseError> {
+        let (token, span) = self.advance().ok_or_else(|| ParseError {
+            message: "expected an expression, found end of input".to_string(),
+            span: self.end,
+        })?;
+
+        match token {
+            Token::Ident(ident) if ident == "not" => {
+                self.expect(&Token::LParen)?;
+                let inner = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                Ok(CfgExpr::Not(Box::new(inner)))
+            }
+            Token::Ident(ident) if ident == "all" => {
+                self.expect(&Token::LParen)?;
+                Ok(CfgExpr::All(self.parse_expr_list()?))
+            }
+            Token::Ident(ident) if ident == "any" => {
+                self.expect(&Token::LParen)?;
+                Ok(CfgExpr::Any(self.parse_expr_list()?))
+            }
+            Token::Ident(ident) => {
+                if matches!(self.peek(), Some((Token::Eq, _))) {
+                    self.advance();
+                    match self.advance() {
+                        Some((Token::String(value), _)) => Ok(CfgExpr::KeyValue(ident, value)),
+                        Some((token, span)) => Err(ParseError {
+                            message: format!(
+                                "expected a string literal after `=`, found {token:?}"
+                            ),
+                            span,
+                        }),
+                        None => Err(ParseError {
+                            message: "expected a string literal after `=`, found end of input"
+                                .to_string(),
+                            span: self.end,
+                        }),
+                    }
+                } else {
+                    Ok(CfgExpr::Ident(ident))
+                }
+            }
+            other => Err(ParseError {
+                message: format!("expected an identifier, found {other:?}"),
+                span,
+            }),
+        }
+    }
+
+    /// Parse a comma-separated list of expressions up to a closing `)`,
+    /// allowing a trailing comma. The opening `(` has already been consumed.
+    fn parse_expr_list(&mut self) -> Result<Vec<CfgExpr>, ParseError> {
+        let mut exprs = Vec::new();
+        if matches!(self.peek(), Some((Token::RParen, _))) {
+            self.advance();
+            return Ok(exprs);
+        }
+        loop {
+            exprs.push(self.parse_expr()?);
+            match self.advance() {
+                Some((Token::Comma, _)) => {
+                    if matches!(self.peek(), Some((Token::RParen, _))) {
+                        self.advance();
+                        return Ok(exprs);
+                    }
+                }
+                Some((Token::RParen, _)) => return Ok(exprs),
+                Some((token, span)) => {
+                    return Err(ParseError {
+                        message: format!("expected `,` or `)`, found {token:?}"),
+                        span,
+                    });
+                }
+                None => {
+                    return Err(ParseError {
+                        message: "expected `,` or `)`, found end of input".to_string(),
+                        span: self.end,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl CfgExpr {
+    /// Parse a `cfg(...)` expression body (without the outer `cfg(...)`
+    /// wrapper), e.g. `target_os = "linux"` or `all(unix, not(windows))`.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input)?;
+        let expr = parser.parse_expr()?;
+        if let Some((token, span)) = parser.peek() {
+            return Err(ParseError {
+                message: format!("unexpected trailing token {token:?}"),
+                span: *span,
+            });
+        }
+        Ok(expr)
+    }
+
+    /// Evaluate this expression against `target`.
+    #[must_use]
+    pub fn evaluate(&self, target: &TargetCfg) -> bool {
+        match self {
+            CfgExpr::Ident(ident) => target.has_bare(ident),
+            CfgExpr::KeyValue(key, value) => target.matches(key, value),
+            CfgExpr::Not(inner) => !inner.evaluate(target),
+            CfgExpr::All(exprs) => exprs.iter().all(|expr| expr.evaluate(target)),
+            CfgExpr::Any(exprs) => exprs.iter().any(|expr| expr.evaluate(target)),
+        }
+    }
+}
+
+/// The predicate set for a target triple: the `target_os`/`target_arch`/etc.
+/// key/value cfgs, plus bare cfgs like `unix`/`windows`.
+#[derive(Debug, Clone)]
+pub struct TargetCfg {
+    pub target_os: String,
+    pub target_arch: String,
+    pub target_family: String,
+    pub target_env: String,
+    pub target_endian: String,
+    pub target_pointer_width: String,
+    bare: Vec<String>,
+}
+
+impl TargetCfg {
+    /// Build the predicate set for `triple`, a Rust target triple such as
+    /// `x86_64-unknown-linux-gnu` or `aarch64-apple-darwin`.
+    #[must_use]
+    pub fn for_triple(triple: &str) -> Self {
+        let target_arch = triple.split('-').next().unwrap_or_default().to_string();
+        let (target_os, target_env) = os_and_env(triple);
+        let target_family = family_for_os(&target_os);
+        // `mipsel`/`mips64el` are little-endian despite starting with
+        // "mips", so key on the "el" suffix rather than the bare prefix.
+        let target_endian = if (target_arch.starts_with("mips") && !target_arch.ends_with("el"))
+            || target_arch == "aarch64_be"
+        {
+            "big".to_string()
+        } else {
+            "little".to_string()
+        };
+        let target_pointer_width = if target_arch.contains("64") {
+            "64".to_string()
+        } else {
+            "32".to_string()
+        };
+
+        let mut bare = Vec::new();
+        match target_family.as_str() {
+            "unix" => bare.push("unix".to_string()),
+            "windows" => bare.push("windows".to_string()),
+            _ => {}
+        }
+
+        Self {
+            target_os,
+            target_arch,
+            target_family,
+            target_env,
+            target_endian,
+            target_pointer_width,
+            bare,
+        }
+    }
+
+    fn has_bare(&self, ident: &str) -> bool {
+        self.bare.iter().any(|b| b == ident)
+    }
+
+    fn matches(&self, key: &str, value: &str) -> bool {
+        match key {
+            "target_os" => self.target_os == value,
+            "target_arch" => self.target_arch == value,
+            "target_family" => self.target_family == value,
+            "target_env" => self.target_env == value,
+            "target_endian" => self.target_endian == value,
+            "target_pointer_width" => self.target_pointer_width == value,
+            _ => false,
+        }
+    }
+}
+
+/// Derive `(target_os, target_env)` from the `-`-separated components of a
+/// target triple, covering the OS families this action targets.
+fn os_and_env(triple: &str) -> (String, String) {
+    let parts: Vec<&str> = triple.split('-').collect();
+    for (i, part) in parts.iter().enumerate() {
+        match *part {
+            "linux" => {
+                let env = parts.get(i + 1).copied().unwrap_or_default().to_string();
+                return ("linux".to_string(), env);
+            }
+            "darwin" => return ("macos".to_string(), String::new()),
+            "ios" => return ("ios".to_string(), String::new()),
+            "windows" => {
+                let env = parts.get(i + 1).copied().unwrap_or_default().to_string();
+                return ("windows".to_string(), env);
+            }
+            _ => {}
+        }
+    }
+    (String::new(), String::new())
+}
+
+fn family_for_os(os: &str) -> String {
+    match os {
+        "linux" | "macos" | "ios" | "android" | "freebsd" | "netbsd" | "openbsd" | "dragonfly"
+        | "illumos" | "solaris" => "unix".to_string(),
+        "windows" => "windows".to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn linux_gnu() -> TargetCfg {
+        TargetCfg::for_triple("x86_64-unknown-linux-gnu")
+    }
+
+    fn macos() -> TargetCfg {
+        TargetCfg::for_triple("aarch64-apple-darwin")
+    }
+
+    fn windows_msvc() -> TargetCfg {
+        TargetCfg::for_triple("x86_64-pc-windows-msvc")
+    }
+
+    #[rstest]
+    #[case("unix", true)]
+    #[case("windows", false)]
+    #[case("target_os = \"linux\"", true)]
+    #[case("target_os = \"macos\"", false)]
+    #[case("target_arch = \"x86_64\"", true)]
+    #[case("target_env = \"gnu\"", true)]
+    #[case("target_pointer_width = \"64\"", true)]
+    fn evaluates_simple_predicates_on_linux(#[case] expr: &str, #[case] expected: bool) {
+        let parsed = CfgExpr::parse(expr).expect("should parse");
+        assert_eq!(parsed.evaluate(&linux_gnu()), expected);
+    }
+
+    #[test]
+    fn not_negates() {
+        let parsed = CfgExpr::parse("not(windows)").unwrap();
+        assert!(parsed.evaluate(&linux_gnu()));
+        assert!(!parsed.evaluate(&windows_msvc()));
+    }
+
+    #[test]
+    fn all_is_and_with_vacuous_true() {
+        assert!(CfgExpr::parse("all()").unwrap().evaluate(&linux_gnu()));
+        let parsed = CfgExpr::parse("all(unix, target_arch = \"x86_64\")").unwrap();
+        assert!(parsed.evaluate(&linux_gnu()));
+        assert!(!parsed.evaluate(&macos()));
+    }
+
+    #[test]
+    fn any_is_or_with_vacuous_false() {
+        assert!(!CfgExpr::parse("any()").unwrap().evaluate(&linux_gnu()));
+        let parsed = CfgExpr::parse(r#"any(target_os = "macos", target_os = "ios")"#).unwrap();
+        assert!(parsed.evaluate(&macos()));
+        assert!(!parsed.evaluate(&linux_gnu()));
+    }
+
+    #[test]
+    fn all_and_any_allow_trailing_comma() {
+        let parsed = CfgExpr::parse("all(unix, windows,)").unwrap();
+        assert!(!parsed.evaluate(&linux_gnu()));
+    }
+
+    #[test]
+    fn nested_expressions_compose() {
+        let parsed =
+            CfgExpr::parse(r#"all(unix, not(any(target_os = "macos", target_os = "ios")))"#)
+                .unwrap();
+        assert!(parsed.evaluate(&linux_gnu()));
+        assert!(!parsed.evaluate(&macos()));
+    }
+
+    #[rstest]
+    #[case("target_os = \"linux", "unterminated string literal")]
+    #[case("target_os = ", "end of input")]
+    #[case("all(unix", "end of input")]
+    #[case("unix)", "unexpected trailing token")]
+    #[case("unix = ", "end of input")]
+    fn reports_parse_errors(#[case] input: &str, #[case] expected_fragment: &str) {
+        let err = CfgExpr::parse(input).unwrap_err();
+        assert!(
+            err.message.contains(expected_fragment) || err.to_string().contains(expected_fragment),
+            "error {err} did not mention {expected_fragment:?}"
+        );
+    }
+
+    #[test]
+    fn macos_and_ios_are_unix_family() {
+        let ios = TargetCfg::for_triple("aarch64-apple-ios");
+        assert_eq!(ios.target_family, "unix");
+        assert_eq!(macos().target_family, "unix");
+    }
+
+    #[rstest]
+    #[case("mips-unknown-linux-gnu", "big")]
+    #[case("mips64-unknown-linux-gnuabi64", "big")]
+    #[case("mipsel-unknown-linux-gnu", "little")]
+    #[case("mips64el-unknown-linux-gnuabi64", "little")]
+    #[case("aarch64_be-unknown-linux-gnu", "big")]
+    #[case("x86_64-unknown-linux-gnu", "little")]
+    fn derives_target_endian(#[case] triple: &str, #[case] expected: &str) {
+        assert_eq!(TargetCfg::for_triple(triple).target_endian, expected);
+    }
+
+    #[test]
+    fn windows_is_not_unix_family() {
+        assert_eq!(windows_msvc().target_family, "windows");
+        assert_eq!(windows_msvc().target_env, "msvc");
+    }
+}
@@ -0,0 +1,72 @@
+//! Minimal coloured unified-diff rendering, shared by the golden-file and
+//! snapshot assertion helpers.
+
+enum DiffLine<'a> {
+    Equal(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Produce a coloured unified-style diff between `expected` and `actual`.
+pub(super) fn unified_diff(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+
+    let mut out = String::new();
+    for line in diff_lines(&expected_lines, &actual_lines) {
+        match line {
+            DiffLine::Equal(text) => {
+                out.push_str("  ");
+                out.push_str(text);
+                out.push('\n');
+            }
+            DiffLine::Removed(text) => {
+                out.push_str("\x1b[31m- ");
+                out.push_str(text);
+                out.push_str("\x1b[0m\n");
+            }
+            DiffLine::Added(text) => {
+                out.push_str("\x1b[32m+ ");
+                out.push_str(text);
+                out.push_str("\x1b[0m\n");
+            }
+        }
+    }
+    out
+}
+
+/// Classic LCS-based line diff: O(n*m), fine for the short strings test
+/// fixtures compare.
+fn diff_lines<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let n = expected.len();
+    let m = actual.len();
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if expected[i] == actual[j] {
+            ops.push(DiffLine::Equal(expected[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffLine::Removed(expected[i]));
+            i += 1;
+        } else {
+            ops.push(DiffLine::Added(actual[j]));
+            j += 1;
+        }
+    }
+    ops.extend(expected[i..].iter().map(|line| DiffLine::Removed(line)));
+    ops.extend(actual[j..].iter().map(|line| DiffLine::Added(line)));
+    ops
+}
@@ -0,0 +1,151 @@
+//! Golden-file assertions with path/target normalization, modelled on
+//! trybuild's `normalize.rs` and cargo-test-support's `compare.rs`.
+//!
+//! [`assert_matches_golden`] compares rendered output against a file stored
+//! under version control, after replacing machine-specific noise (the
+//! workspace root, `CARGO_TARGET_DIR`, the host target triple, and
+//! `\r\n` line endings) with stable placeholders. On mismatch it prints a
+//! coloured unified diff; setting `UPDATE_GOLDEN=1` rewrites the golden file
+//! instead of asserting.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use super::diff::unified_diff;
+
+const ROOT_PLACEHOLDER: &str = "[ROOT]";
+const TARGET_DIR_PLACEHOLDER: &str = "[TARGET_DIR]";
+const HOST_TRIPLE_PLACEHOLDER: &str = "[HOST_TRIPLE]";
+
+/// Replace workspace-root, target-dir, host-triple, and CRLF noise with
+/// stable placeholders so golden comparisons are portable across machines.
+#[must_use]
+pub fn normalize(text: &str) -> String {
+    let mut normalized = text.replace("\r\n", "\n");
+    normalized = normalized.replace(&workspace_root(), ROOT_PLACEHOLDER);
+    normalized = normalized.replace(&target_dir(), TARGET_DIR_PLACEHOLDER);
+    if let Some(triple) = host_triple() {
+        normalized = normalized.replace(&triple, HOST_TRIPLE_PLACEHOLDER);
+    }
+    normalized
+}
+
+/// Assert that `actual` matches the contents of `golden_path`, after
+/// normalization. Set `UPDATE_GOLDEN=1` to rewrite the golden file with
+/// `actual` instead of asserting.
+pub fn assert_matches_golden(actual: &str, golden_path: impl AsRef<Path>) {
+    let golden_path = golden_path.as_ref();
+    let normalized_actual = normalize(actual);
+
+    if env::var("UPDATE_GOLDEN").as_deref() == Ok("1") {
+        fs::write(golden_path, &normalized_actual)
+            .unwrap_or_else(|err| panic!("failed to write golden file {}: {err}", golden_path.display()));
+        return;
+    }
+
+    let expected = fs::read_to_string(golden_path).unwrap_or_else(|err| {
+        panic!(
+            "failed to read golden file {} (set UPDATE_GOLDEN=1 to create it): {err}",
+            golden_path.display()
+        )
+    });
+    let normalized_expected = normalize(&expected);
+
+    assert!(
+        normalized_actual == normalized_expected,
+        "output does not match golden file {}:\n{}\n(set UPDATE_GOLDEN=1 to rewrite it)",
+        golden_path.display(),
+        unified_diff(&normalized_expected, &normalized_actual),
+    );
+}
+
+fn workspace_root() -> String {
+    env!("CARGO_MANIFEST_DIR").to_string()
+}
+
+fn target_dir() -> String {
+    // Never fall back to the bare relative literal "target": it's a
+    // substring of ordinary output (man pages, "target triple", etc.) and
+    // would corrupt anything containing that word. Cargo's actual default,
+    // absent `CARGO_TARGET_DIR`, is `<workspace root>/target`.
+    env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| format!("{}/target", workspace_root()))
+}
+
+fn host_triple() -> Option<String> {
+    // SAFETY net: this is a test-only probe of the toolchain, not a
+    // production call site, so it is exempt from the
+    // `test_support::command::create_command` PATH-hijack guard.
+    #[allow(clippy::disallowed_methods)]
+    let output = std::process::Command::new("rustc").arg("-vV").output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("host: "))
+        .map(|triple| triple.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, OnceLock};
+    use tempfile::NamedTempFile;
+
+    /// `assert_matches_golden` reads the process-global `UPDATE_GOLDEN` var
+    /// on every call, so any test exercising it must serialize against
+    /// `update_golden_rewrites_the_file`, which sets that var — otherwise a
+    /// concurrently-run test can observe it set and silently take the
+    /// rewrite branch instead of asserting.
+    fn env_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[test]
+    fn matches_identical_golden_content() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "Hello, world!\n").unwrap();
+        assert_matches_golden("Hello, world!\n", file.path());
+    }
+
+    #[test]
+    fn normalizes_workspace_root() {
+        let actual = format!("{}/target/debug/rust-toy-app", env!("CARGO_MANIFEST_DIR"));
+        assert_eq!(normalize(&actual), "[ROOT]/target/debug/rust-toy-app");
+    }
+
+    #[test]
+    fn does_not_corrupt_unrelated_occurrences_of_the_word_target() {
+        let text = "please specify a target before building\n";
+        assert_eq!(normalize(text), text);
+    }
+
+    #[test]
+    fn normalizes_crlf_line_endings() {
+        assert_eq!(normalize("line one\r\nline two\r\n"), "line one\nline two\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match golden file")]
+    fn mismatched_content_panics_with_diff() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "expected line\n").unwrap();
+        assert_matches_golden("actual line\n", file.path());
+    }
+
+    #[test]
+    fn update_golden_rewrites_the_file() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "stale content\n").unwrap();
+
+        // SAFETY: serialized by `env_lock` above.
+        unsafe { env::set_var("UPDATE_GOLDEN", "1") };
+        assert_matches_golden("fresh content\n", file.path());
+        // SAFETY: serialized by `env_lock` above.
+        unsafe { env::remove_var("UPDATE_GOLDEN") };
+
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), "fresh content\n");
+    }
+}
@@ -1,5 +1,9 @@
 //! Shared helpers, fixtures, and utilities for rust-toy-app integration tests.
 
+mod diff;
+pub mod golden;
+pub mod snapshot;
+
 use glob::glob;
 use std::path::Path;
 
@@ -0,0 +1,254 @@
+//! Template-based snapshot assertions with redaction and wildcard matching,
+//! modelled on trybuild's `normalize.rs`.
+//!
+//! Unlike [`super::golden::assert_matches_golden`], which does an exact
+//! comparison after normalizing a fixed set of placeholders,
+//! [`assert_matches_snapshot`] redacts more volatile substrings (the repo
+//! root, a temp directory, semver-looking tokens, ISO dates) and tolerates
+//! a literal `[..]` wildcard inside an expected line, matching any run of
+//! characters on that line. This suits CLI output where only part of a
+//! line is unpredictable (e.g. `built vX.Y.Z in [..]`).
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use super::diff::unified_diff;
+
+const WILDCARD: &str = "[..]";
+
+/// Redact known-volatile substrings (the repo root, a temp directory,
+/// semver-looking tokens, ISO dates) with stable placeholders, and
+/// normalize `\` to `/` in path-like text.
+#[must_use]
+pub fn redact(text: &str, repo_root: &Path, temp_dir: &Path) -> String {
+    let mut redacted = text.replace('\\', "/");
+    redacted = redacted.replace(&path_str(repo_root), "[ROOT]");
+    redacted = redacted.replace(&path_str(temp_dir), "[DIR]");
+    redacted = redact_tokens(&redacted, is_semver_token, "[VERSION]");
+    redact_tokens(&redacted, is_iso_date_token, "[DATE]")
+}
+
+fn path_str(path: &Path) -> String {
+    path.display().to_string().replace('\\', "/")
+}
+
+/// Replace whitespace-delimited tokens matching `is_match` with
+/// `placeholder`, preserving the original trailing whitespace/punctuation.
+fn redact_tokens(text: &str, is_match: impl Fn(&str) -> bool, placeholder: &str) -> String {
+    text.split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let trimmed = word.trim_end();
+            let suffix = &word[trimmed.len()..];
+            if is_match(trimmed) {
+                format!("{placeholder}{suffix}")
+            } else {
+                word.to_string()
+            }
+        })
+        .collect()
+}
+
+fn is_semver_token(token: &str) -> bool {
+    let core = token.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-' && c != '+');
+    let version = core.split(['-', '+']).next().unwrap_or(core);
+    let segments: Vec<&str> = version.split('.').collect();
+    segments.len() == 3
+        && segments
+            .iter()
+            .all(|segment| !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_iso_date_token(token: &str) -> bool {
+    let core = token.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-');
+    let bytes = core.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && core[0..4].bytes().all(|b| b.is_ascii_digit())
+        && core[5..7].bytes().all(|b| b.is_ascii_digit())
+        && core[8..10].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Compare `actual` against `expected`, line by line, honouring a literal
+/// `[..]` wildcard inside an expected line (it matches any run of
+/// characters on that line).
+fn matches_with_wildcards(expected: &str, actual: &str) -> bool {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    expected_lines.len() == actual_lines.len()
+        && expected_lines
+            .iter()
+            .zip(actual_lines.iter())
+            .all(|(expected_line, actual_line)| line_matches(expected_line, actual_line))
+}
+
+fn line_matches(expected_line: &str, actual_line: &str) -> bool {
+    if !expected_line.contains(WILDCARD) {
+        return expected_line == actual_line;
+    }
+
+    let segments: Vec<&str> = expected_line.split(WILDCARD).collect();
+    let last = segments.len() - 1;
+    let mut rest = actual_line;
+    for (i, segment) in segments.iter().enumerate() {
+        if i == 0 {
+            let Some(stripped) = rest.strip_prefix(segment) else {
+                return false;
+            };
+            rest = stripped;
+        } else if i == last {
+            return rest.ends_with(segment);
+        } else if let Some(pos) = rest.find(segment) {
+            rest = &rest[pos + segment.len()..];
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+/// Assert that `actual`, after redaction, matches the template stored at
+/// `expected_path` (which may contain `[..]` wildcards). Set
+/// `UPDATE_SNAPSHOTS=1` to rewrite the template with the redacted `actual`
+/// instead of asserting.
+pub fn assert_matches_snapshot(
+    actual: &str,
+    expected_path: impl AsRef<Path>,
+    repo_root: &Path,
+    temp_dir: &Path,
+) {
+    let expected_path = expected_path.as_ref();
+    let redacted_actual = redact(actual, repo_root, temp_dir);
+
+    if env::var("UPDATE_SNAPSHOTS").as_deref() == Ok("1") {
+        fs::write(expected_path, &redacted_actual)
+            .unwrap_or_else(|err| panic!("failed to write snapshot {}: {err}", expected_path.display()));
+        return;
+    }
+
+    let expected = fs::read_to_string(expected_path).unwrap_or_else(|err| {
+        panic!(
+            "failed to read snapshot {} (set UPDATE_SNAPSHOTS=1 to create it): {err}",
+            expected_path.display()
+        )
+    });
+
+    assert!(
+        matches_with_wildcards(&expected, &redacted_actual),
+        "output does not match snapshot {}:\n{}\n(set UPDATE_SNAPSHOTS=1 to rewrite it)",
+        expected_path.display(),
+        unified_diff(&expected, &redacted_actual),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::{Mutex, OnceLock};
+    use tempfile::NamedTempFile;
+
+    /// `assert_matches_snapshot` reads the process-global `UPDATE_SNAPSHOTS`
+    /// var on every call, so any test exercising it must serialize against
+    /// `update_snapshots_rewrites_the_file`, which sets that var — otherwise
+    /// a concurrently-run test can observe it set and silently take the
+    /// rewrite branch instead of asserting.
+    fn env_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn repo_root() -> PathBuf {
+        PathBuf::from("/home/dev/shared-actions")
+    }
+
+    fn temp_dir() -> PathBuf {
+        PathBuf::from("/tmp/snapshot-test-abc123")
+    }
+
+    #[test]
+    fn redacts_repo_root_and_temp_dir() {
+        let actual = format!(
+            "built {}/target/debug in {}",
+            repo_root().display(),
+            temp_dir().display()
+        );
+        assert_eq!(redact(&actual, &repo_root(), &temp_dir()), "built [ROOT]/target/debug in [DIR]");
+    }
+
+    #[test]
+    fn redacts_semver_tokens() {
+        assert_eq!(
+            redact("rust-toy-app 1.2.3\n", &repo_root(), &temp_dir()),
+            "rust-toy-app [VERSION]\n"
+        );
+        assert_eq!(
+            redact("version 1.2.3-rc.1 released\n", &repo_root(), &temp_dir()),
+            "version [VERSION] released\n"
+        );
+    }
+
+    #[test]
+    fn redacts_iso_dates() {
+        assert_eq!(
+            redact("generated on 2026-07-26\n", &repo_root(), &temp_dir()),
+            "generated on [DATE]\n"
+        );
+    }
+
+    #[test]
+    fn normalizes_windows_path_separators() {
+        assert_eq!(
+            redact(r"C:\Users\dev\out.txt", &repo_root(), &temp_dir()),
+            "C:/Users/dev/out.txt"
+        );
+    }
+
+    #[test]
+    fn wildcard_matches_any_run_of_characters() {
+        assert!(line_matches("built in [..]ms", "built in 42ms"));
+        assert!(line_matches("[..] done", "all tasks done"));
+        assert!(!line_matches("built in [..]ms", "built in 42 seconds"));
+    }
+
+    #[test]
+    fn assert_matches_snapshot_passes_for_identical_content() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "Hello, world!\n").unwrap();
+        assert_matches_snapshot("Hello, world!\n", file.path(), &repo_root(), &temp_dir());
+    }
+
+    #[test]
+    fn assert_matches_snapshot_honours_wildcard_template() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "finished in [..]ms\n").unwrap();
+        assert_matches_snapshot("finished in 17ms\n", file.path(), &repo_root(), &temp_dir());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match snapshot")]
+    fn assert_matches_snapshot_panics_on_mismatch() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "expected\n").unwrap();
+        assert_matches_snapshot("actual\n", file.path(), &repo_root(), &temp_dir());
+    }
+
+    #[test]
+    fn update_snapshots_rewrites_the_file() {
+        let _guard = env_lock().lock().unwrap();
+        let file = NamedTempFile::new().unwrap();
+        fs::write(file.path(), "stale\n").unwrap();
+
+        // SAFETY: serialized by `env_lock` above.
+        unsafe { env::set_var("UPDATE_SNAPSHOTS", "1") };
+        assert_matches_snapshot("fresh\n", file.path(), &repo_root(), &temp_dir());
+        // SAFETY: serialized by `env_lock` above.
+        unsafe { env::remove_var("UPDATE_SNAPSHOTS") };
+
+        assert_eq!(fs::read_to_string(file.path()).unwrap(), "fresh\n");
+    }
+}
@@ -0,0 +1,233 @@
+//! Test-support module that discovers which Rust target triples this host
+//! can build and/or run, modelled on cargo-test-support's
+//! `cross_compile.rs`. Integration tests use this instead of hardcoding "if
+//! not x86_64 and no container runtime, skip", so the target matrix can grow
+//! without each test re-deriving its own skip logic.
+//!
+//! Consumers pick the slice of this API they need (e.g. `test_case.rs`
+//! currently only reads `host_triple`), so unused items are allowed here
+//! rather than warning per integration-test crate that pulls in
+//! `mod cross_compile;`.
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use crate::runtime::container::ContainerRuntime;
+use crate::test_support::command::create_command;
+
+/// The host triple, as reported by `rustc -vV`. Cached for the process
+/// lifetime.
+#[must_use]
+pub fn host_triple() -> &'static str {
+    static HOST: OnceLock<String> = OnceLock::new();
+    HOST.get_or_init(|| {
+        let output = create_command("rustc")
+            .arg("-vV")
+            .output()
+            .expect("failed to run `rustc -vV`");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .expect("`rustc -vV` should report a host triple")
+            .trim()
+            .to_string()
+    })
+}
+
+/// Target triples with `rustup target add` already run. Empty if `rustup`
+/// is unavailable (e.g. a non-rustup toolchain), in which case only the
+/// host triple is considered installed.
+fn installed_targets() -> &'static [String] {
+    static TARGETS: OnceLock<Vec<String>> = OnceLock::new();
+    TARGETS.get_or_init(|| {
+        create_command("rustup")
+            .arg("target")
+            .arg("list")
+            .arg("--installed")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
+/// True if `target`'s std is installed, or it is the host triple (which
+/// always builds without an explicit `rustup target add`).
+#[must_use]
+pub fn has_std(target: &str) -> bool {
+    target == host_triple() || installed_targets().iter().any(|t| t == target)
+}
+
+/// Non-host targets whose cross-linker we don't assume is on `PATH`; these
+/// are built inside a container instead.
+fn needs_container(target: &str) -> bool {
+    target != host_triple() && !target.contains("windows") && !target.ends_with("darwin") && !target.contains("ios")
+}
+
+/// True if a usable linker (or container runtime, for targets that need
+/// one) is available to actually *build* `target`, beyond just having std
+/// installed.
+#[must_use]
+pub fn can_build(target: &str) -> bool {
+    if !has_std(target) {
+        return false;
+    }
+    if target == host_triple() || !needs_container(target) {
+        return true;
+    }
+    ContainerRuntime::detect().is_some()
+}
+
+/// True if the artifact built for `target` can actually be *executed* on
+/// this host: natively if it's the host triple, otherwise only if a
+/// container runtime is available to run it under.
+#[must_use]
+pub fn can_run(target: &str) -> bool {
+    target == host_triple() || ContainerRuntime::detect().is_some()
+}
+
+/// A precise, user-facing reason why `target` should be skipped, or `None`
+/// if it can be built.
+#[must_use]
+pub fn disabled_reason(target: &str) -> Option<String> {
+    if !has_std(target) {
+        return Some(format!(
+            "target {target} is not installed (run `rustup target add {target}`)"
+        ));
+    }
+    if can_build(target) {
+        None
+    } else {
+        Some(format!(
+            "target {target} needs a container runtime (docker or podman) to cross-build"
+        ))
+    }
+}
+
+/// The `CARGO_TARGET_<TRIPLE>_RUNNER` environment variable cargo reads to
+/// choose a runner for `triple`, e.g.
+/// `CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER`.
+#[must_use]
+pub fn runner_env_var(triple: &str) -> String {
+    format!("CARGO_TARGET_{}_RUNNER", triple.to_uppercase().replace('-', "_"))
+}
+
+fn is_on_path(program: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path_var| {
+        std::env::split_paths(&path_var).any(|dir| dir.join(program).is_file())
+    })
+}
+
+/// A runner registered for `triple`: either explicitly via its
+/// `CARGO_TARGET_<TRIPLE>_RUNNER` env var, or a conventionally-named
+/// `qemu-<arch>-static`/`qemu-<arch>` binary found on `PATH`.
+#[must_use]
+pub fn registered_runner(triple: &str) -> Option<String> {
+    if let Ok(runner) = std::env::var(runner_env_var(triple)) {
+        return Some(runner);
+    }
+    let arch = triple.split('-').next()?;
+    [format!("qemu-{arch}-static"), format!("qemu-{arch}")]
+        .into_iter()
+        .find(|candidate| is_on_path(candidate))
+}
+
+/// True if `triple` can be built *and*, when it isn't the host triple, the
+/// resulting binary can actually be run here: via a registered runner
+/// (qemu-user or the `cross` container runner) or a container runtime.
+#[must_use]
+pub fn cross_target_available(triple: &str) -> bool {
+    can_build(triple)
+        && (triple == host_triple() || registered_runner(triple).is_some() || ContainerRuntime::detect().is_some())
+}
+
+/// How a triple was exercised by [`run_matrix`].
+#[derive(Debug, Clone)]
+pub enum MatrixOutcome {
+    /// Built and run natively on the host triple.
+    Native,
+    /// Built cross-target and run under `runner` (qemu-user or similar).
+    Emulated { runner: String },
+    /// Not attempted; see the attached reason.
+    Skipped { reason: String },
+}
+
+/// The outcome of driving a build across a target matrix via [`run_matrix`].
+#[derive(Debug, Clone, Default)]
+pub struct MatrixReport {
+    pub outcomes: Vec<(String, MatrixOutcome)>,
+}
+
+impl MatrixReport {
+    /// Triples that were built and run natively.
+    #[must_use]
+    pub fn native(&self) -> Vec<&str> {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, MatrixOutcome::Native))
+            .map(|(triple, _)| triple.as_str())
+            .collect()
+    }
+
+    /// Triples that were built and run under emulation.
+    #[must_use]
+    pub fn emulated(&self) -> Vec<&str> {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, MatrixOutcome::Emulated { .. }))
+            .map(|(triple, _)| triple.as_str())
+            .collect()
+    }
+
+    /// Triples that were skipped, paired with the reason.
+    #[must_use]
+    pub fn skipped(&self) -> Vec<(&str, &str)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(triple, outcome)| match outcome {
+                MatrixOutcome::Skipped { reason } => Some((triple.as_str(), reason.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Drive `build` across `triples`, skipping (with a precise, logged reason)
+/// any target that can't be built here instead of failing the whole job.
+/// `build` receives the triple and, when cross-building under emulation,
+/// the runner that should be threaded through `CARGO_TARGET_<TRIPLE>_RUNNER`.
+pub fn run_matrix(
+    triples: &[&str],
+    mut build: impl FnMut(&str, Option<&str>) -> Result<(), String>,
+) -> Result<MatrixReport, String> {
+    let mut report = MatrixReport::default();
+    for &triple in triples {
+        if let Some(reason) = disabled_reason(triple) {
+            eprintln!("skipping {triple}: {reason}");
+            report
+                .outcomes
+                .push((triple.to_string(), MatrixOutcome::Skipped { reason }));
+            continue;
+        }
+
+        let runner = (triple != host_triple())
+            .then(|| registered_runner(triple))
+            .flatten();
+        build(triple, runner.as_deref())?;
+
+        let outcome = match runner {
+            Some(runner) => MatrixOutcome::Emulated { runner },
+            None => MatrixOutcome::Native,
+        };
+        report.outcomes.push((triple.to_string(), outcome));
+    }
+    Ok(report)
+}
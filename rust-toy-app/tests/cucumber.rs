@@ -23,11 +23,14 @@
 //!
 //! [`generate-coverage`]: https://github.com/leynos/shared-actions/tree/main/.github/actions/generate-coverage
 
-use std::process::{Command, Output};
+use std::process::Output;
 
 use cucumber::{given, then, when, World};
 use rust_toy_app::cli::Cli;
 
+mod test_support;
+use test_support::command::{create_command, split_args};
+
 /// Path to the Gherkin feature files, relative to the crate root.
 ///
 /// This path must match the `cucumber-rs-features` input when using the
@@ -104,8 +107,8 @@ fn binary_exists(world: &mut GreetingWorld) {
 
 #[when(expr = "I run it with {string}")]
 fn run_with_args(world: &mut GreetingWorld, args: String) {
-    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rust-toy-app"));
-    for arg in args.split_whitespace() {
+    let mut cmd = create_command(env!("CARGO_BIN_EXE_rust-toy-app"));
+    for arg in split_args(&args) {
         cmd.arg(arg);
     }
     world.output = Some(cmd.output().expect("failed to execute binary"));
@@ -113,7 +116,7 @@ fn run_with_args(world: &mut GreetingWorld, args: String) {
 
 #[when("I run it without arguments")]
 fn run_without_args(world: &mut GreetingWorld) {
-    let output = Command::new(env!("CARGO_BIN_EXE_rust-toy-app"))
+    let output = create_command(env!("CARGO_BIN_EXE_rust-toy-app"))
         .output()
         .expect("failed to execute binary");
     world.output = Some(output);
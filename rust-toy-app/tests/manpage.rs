@@ -1,10 +1,12 @@
+mod test_support;
+
 use assert_cmd::prelude::*;
 use glob::glob;
-use std::process::Command;
+use test_support::command::create_command;
 
 #[test]
 fn manpage_generated() {
-    Command::new("cargo")
+    create_command("cargo")
         .arg("build")
         .current_dir(env!("CARGO_MANIFEST_DIR"))
         .assert()
@@ -1,12 +1,13 @@
 mod common;
+mod test_support;
 
 use assert_cmd::prelude::*;
 use common::assert_manpage_exists;
-use std::process::Command;
+use test_support::command::create_command;
 
 #[test]
 fn generates_manpage() {
-    Command::new("cargo")
+    create_command("cargo")
         .arg("build")
         .assert()
         .success();
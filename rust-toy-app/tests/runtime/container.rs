@@ -0,0 +1,460 @@
+//! Container-runtime abstraction with RAII lifecycle management, modelled on
+//! cargo-test-support's `containers.rs`. Lets integration tests spin up a
+//! container, block until it reports ready, `exec` commands inside it, and
+//! be certain it is torn down even when an assertion panics.
+//!
+//! Not yet exercised by a test in this checkout (no E2E suite here drives a
+//! real container), so the public API is allowed to go unused for now
+//! rather than warning on every integration-test crate that pulls in
+//! `mod runtime;`.
+#![allow(dead_code)]
+
+use std::fs;
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::runtime_available;
+use crate::test_support::command::create_command;
+
+/// A counter appended to container/image names alongside the process id, so
+/// that two harnesses started within the same test binary don't collide on
+/// name even though they share a pid.
+fn next_instance_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Which container engine to drive. Use [`ContainerRuntime::detect`] to
+/// auto-select docker, falling back to podman.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    /// Auto-select a runtime, preferring docker over podman.
+    #[must_use]
+    pub fn detect() -> Option<Self> {
+        if runtime_available("docker") {
+            Some(Self::Docker)
+        } else if runtime_available("podman") {
+            Some(Self::Podman)
+        } else {
+            None
+        }
+    }
+
+    fn binary(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+
+    fn command(self) -> Command {
+        create_command(self.binary())
+    }
+}
+
+/// How to decide that a started container has finished coming up.
+pub enum Readiness {
+    /// Poll `argv[0] argv[1..]`, executed inside the container, until it
+    /// exits successfully.
+    Command(Vec<String>),
+    /// Wait until `127.0.0.1:{port}` accepts a TCP connection. `port` must
+    /// have been published to the host via `ContainerBuilder::port` or
+    /// `HarnessBuilder::port`, or this will spin until the timeout.
+    TcpPort(u16),
+    /// Wait until the container's combined stdout/stderr logs contain
+    /// `needle`.
+    LogLine(String),
+}
+
+/// Builder for a [`Container`].
+pub struct ContainerBuilder {
+    runtime: ContainerRuntime,
+    image: String,
+    env: Vec<(String, String)>,
+    volumes: Vec<(String, String)>,
+    ports: Vec<(u16, u16)>,
+    readiness: Option<Readiness>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl ContainerBuilder {
+    #[must_use]
+    pub fn new(runtime: ContainerRuntime, image: impl Into<String>) -> Self {
+        Self {
+            runtime,
+            image: image.into(),
+            env: Vec::new(),
+            volumes: Vec::new(),
+            ports: Vec::new(),
+            readiness: None,
+            poll_interval: Duration::from_millis(200),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Add an environment variable to pass to the container.
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Mount `host_path` at `container_path`.
+    #[must_use]
+    pub fn volume(mut self, host_path: impl Into<String>, container_path: impl Into<String>) -> Self {
+        self.volumes.push((host_path.into(), container_path.into()));
+        self
+    }
+
+    /// Publish `host_port` mapped to `container_port`, so a
+    /// [`Readiness::TcpPort`] probe against `host_port` can actually observe
+    /// the container.
+    #[must_use]
+    pub fn port(mut self, host_port: u16, container_port: u16) -> Self {
+        self.ports.push((host_port, container_port));
+        self
+    }
+
+    /// Set how readiness is probed after the container starts.
+    #[must_use]
+    pub fn readiness(mut self, readiness: Readiness) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
+    /// Override the default 30s readiness timeout.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Start the container and, if a readiness check was configured, block
+    /// until it passes. Returns an RAII handle that stops and removes the
+    /// container on drop.
+    pub fn start(self) -> Result<Container, String> {
+        let name = format!("shared-actions-test-{}-{}", std::process::id(), self.image_slug());
+        let mut cmd = self.runtime.command();
+        cmd.arg("run").arg("-d").arg("--name").arg(&name);
+        for (key, value) in &self.env {
+            cmd.arg("-e").arg(format!("{key}={value}"));
+        }
+        for (host, container) in &self.volumes {
+            cmd.arg("-v").arg(format!("{host}:{container}"));
+        }
+        for (host_port, container_port) in &self.ports {
+            cmd.arg("-p").arg(format!("{host_port}:{container_port}"));
+        }
+        cmd.arg(&self.image);
+
+        let output = cmd
+            .output()
+            .map_err(|err| format!("failed to start container from {}: {err}", self.image))?;
+        if !output.status.success() {
+            return Err(format!(
+                "container {} failed to start: {}",
+                self.image,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let container = Container {
+            runtime: self.runtime,
+            name,
+        };
+
+        if let Some(readiness) = &self.readiness {
+            container.wait_ready(readiness, self.poll_interval, self.timeout)?;
+        }
+
+        Ok(container)
+    }
+
+    fn image_slug(&self) -> String {
+        self.image.replace(['/', ':'], "-")
+    }
+}
+
+/// RAII handle to a running container. Dropping it stops and removes the
+/// container, even if the test that owns it panics.
+pub struct Container {
+    runtime: ContainerRuntime,
+    name: String,
+}
+
+impl Container {
+    /// Start building a container from `image` using `runtime`.
+    #[must_use]
+    pub fn builder(runtime: ContainerRuntime, image: impl Into<String>) -> ContainerBuilder {
+        ContainerBuilder::new(runtime, image)
+    }
+
+    /// Run `program` with `args` inside the container, capturing output.
+    pub fn exec(&self, program: &str, args: &[&str]) -> std::io::Result<Output> {
+        self.runtime
+            .command()
+            .arg("exec")
+            .arg(&self.name)
+            .arg(program)
+            .args(args)
+            .output()
+    }
+
+    fn wait_ready(&self, readiness: &Readiness, poll_interval: Duration, timeout: Duration) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let ready = match readiness {
+                Readiness::Command(argv) => self.probe_command(argv),
+                Readiness::TcpPort(port) => TcpStream::connect(("127.0.0.1", *port)).is_ok(),
+                Readiness::LogLine(needle) => self
+                    .logs()
+                    .map(|logs| logs.contains(needle.as_str()))
+                    .unwrap_or(false),
+            };
+            if ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "container {} did not become ready within {timeout:?}",
+                    self.name
+                ));
+            }
+            thread::sleep(poll_interval);
+        }
+    }
+
+    fn probe_command(&self, argv: &[String]) -> bool {
+        let Some((program, args)) = argv.split_first() else {
+            return false;
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.exec(program, &args)
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Fetch the container's combined stdout/stderr logs, e.g. to attach to
+    /// a test failure for diagnostics.
+    pub fn logs(&self) -> std::io::Result<String> {
+        let output = self.runtime.command().arg("logs").arg(&self.name).output()?;
+        Ok(format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        let _ = self.runtime.command().arg("rm").arg("-f").arg(&self.name).status();
+    }
+}
+
+/// Builder for a [`ContainerHarness`]: builds an image from an inline
+/// Dockerfile, then starts a container from it with published ports.
+pub struct HarnessBuilder {
+    runtime: ContainerRuntime,
+    dockerfile: String,
+    env: Vec<(String, String)>,
+    ports: Vec<(u16, u16)>,
+    readiness: Option<Readiness>,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl HarnessBuilder {
+    #[must_use]
+    pub fn new(runtime: ContainerRuntime, dockerfile: impl Into<String>) -> Self {
+        Self {
+            runtime,
+            dockerfile: dockerfile.into(),
+            env: Vec::new(),
+            ports: Vec::new(),
+            readiness: None,
+            poll_interval: Duration::from_millis(200),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Add an environment variable to pass to the container.
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Publish `host_port` mapped to `container_port`.
+    #[must_use]
+    pub fn port(mut self, host_port: u16, container_port: u16) -> Self {
+        self.ports.push((host_port, container_port));
+        self
+    }
+
+    /// Set how readiness is probed after the container starts.
+    #[must_use]
+    pub fn readiness(mut self, readiness: Readiness) -> Self {
+        self.readiness = Some(readiness);
+        self
+    }
+
+    /// Override the default 30s readiness timeout.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Build the image from the inline Dockerfile and start a uniquely
+    /// named container from it, blocking on readiness if configured. On
+    /// readiness failure, the container's logs are attached to the error
+    /// before it is torn down.
+    pub fn start(self) -> Result<ContainerHarness, String> {
+        let instance = next_instance_id();
+        let tag = format!("shared-actions-test-image-{}-{instance}", std::process::id());
+        let build_dir = tempfile::tempdir()
+            .map_err(|err| format!("failed to create build context: {err}"))?;
+        fs::write(build_dir.path().join("Dockerfile"), &self.dockerfile)
+            .map_err(|err| format!("failed to write Dockerfile: {err}"))?;
+
+        let build_output = self
+            .runtime
+            .command()
+            .arg("build")
+            .arg("-t")
+            .arg(&tag)
+            .arg(build_dir.path())
+            .output()
+            .map_err(|err| format!("failed to build image {tag}: {err}"))?;
+        if !build_output.status.success() {
+            return Err(format!(
+                "image build failed: {}",
+                String::from_utf8_lossy(&build_output.stderr)
+            ));
+        }
+
+        let name = format!("shared-actions-test-{}-{instance}", std::process::id());
+        let mut cmd = self.runtime.command();
+        cmd.arg("run").arg("-d").arg("--name").arg(&name);
+        for (key, value) in &self.env {
+            cmd.arg("-e").arg(format!("{key}={value}"));
+        }
+        for (host_port, container_port) in &self.ports {
+            cmd.arg("-p").arg(format!("{host_port}:{container_port}"));
+        }
+        cmd.arg(&tag);
+
+        let run_output = cmd
+            .output()
+            .map_err(|err| format!("failed to start container from {tag}: {err}"))?;
+        if !run_output.status.success() {
+            return Err(format!(
+                "container failed to start: {}",
+                String::from_utf8_lossy(&run_output.stderr)
+            ));
+        }
+
+        let container = Container {
+            runtime: self.runtime,
+            name,
+        };
+
+        if let Some(readiness) = &self.readiness {
+            if let Err(err) = container.wait_ready(readiness, self.poll_interval, self.timeout) {
+                let logs = container.logs().unwrap_or_default();
+                // `container` drops here, stopping and removing it.
+                return Err(format!("{err}\n--- container logs ---\n{logs}"));
+            }
+        }
+
+        Ok(ContainerHarness { container, tag })
+    }
+}
+
+/// RAII handle returned by [`HarnessBuilder::start`]. Dropping it stops and
+/// removes both the container and the image built for it.
+pub struct ContainerHarness {
+    container: Container,
+    tag: String,
+}
+
+impl Drop for ContainerHarness {
+    fn drop(&mut self) {
+        // Force-remove the container up front: `Container`'s own `Drop`
+        // only runs once this method returns, and an image can't be
+        // removed while a container still references it.
+        let _ = self
+            .container
+            .runtime
+            .command()
+            .arg("rm")
+            .arg("-f")
+            .arg(&self.container.name)
+            .status();
+        let _ = self
+            .container
+            .runtime
+            .command()
+            .arg("rmi")
+            .arg("-f")
+            .arg(&self.tag)
+            .status();
+    }
+}
+
+impl ContainerHarness {
+    /// Start building a harness that builds `dockerfile` and runs it.
+    #[must_use]
+    pub fn builder(runtime: ContainerRuntime, dockerfile: impl Into<String>) -> HarnessBuilder {
+        HarnessBuilder::new(runtime, dockerfile)
+    }
+
+    /// Run `program` with `args` inside the container, capturing output.
+    pub fn exec(&self, program: &str, args: &[&str]) -> std::io::Result<Output> {
+        self.container.exec(program, args)
+    }
+
+    /// Copy a file from the host into the container.
+    pub fn copy_in(&self, host_path: &Path, container_path: &str) -> std::io::Result<Output> {
+        self.container
+            .runtime
+            .command()
+            .arg("cp")
+            .arg(host_path)
+            .arg(format!("{}:{container_path}", self.container.name))
+            .output()
+    }
+
+    /// Copy a file out of the container onto the host.
+    pub fn copy_out(&self, container_path: &str, host_path: &Path) -> std::io::Result<Output> {
+        self.container
+            .runtime
+            .command()
+            .arg("cp")
+            .arg(format!("{}:{container_path}", self.container.name))
+            .arg(host_path)
+            .output()
+    }
+
+    /// Fetch the container's logs, e.g. to attach to a test failure.
+    pub fn logs(&self) -> std::io::Result<String> {
+        self.container.logs()
+    }
+
+    /// The tag of the image this harness built.
+    #[must_use]
+    pub fn image_tag(&self) -> &str {
+        &self.tag
+    }
+}
@@ -1,12 +1,18 @@
 //! Test utilities that detect container runtime availability using the action's
-//! Python helpers. The probe first attempts to invoke `uv run` with the action
+//! Python helpers, and a [`container`] subsystem for driving containers in
+//! integration tests once a runtime is known to be present.
+//!
+//! The availability probe first attempts to invoke `uv run` with the action
 //! sources, falling back to the system Python interpreter when `uv` is
 //! unavailable, mirroring the runtime detection logic used in production.
 
+pub mod container;
+
 use std::env;
 use std::ffi::OsString;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+
+use crate::test_support::command::create_command;
 
 #[cfg(test)]
 use std::sync::{Mutex, OnceLock};
@@ -46,7 +52,7 @@ fn python_interpreter() -> OsString {
 
 fn run_with_uv(script: &str, runtime: &str, module_dir: &Path) -> Option<bool> {
     let action_dir = module_dir.parent().unwrap_or(module_dir);
-    let status = Command::new(uv_binary())
+    let status = create_command(uv_binary())
         .arg("run")
         .arg("--with")
         .arg("typer")
@@ -71,7 +77,7 @@ fn run_with_uv(script: &str, runtime: &str, module_dir: &Path) -> Option<bool> {
 
 fn run_with_python(script: &str, runtime: &str, module_dir: &Path) -> bool {
     let action_dir = module_dir.parent().unwrap_or(module_dir);
-    match Command::new(python_interpreter())
+    match create_command(python_interpreter())
         .arg("-c")
         .arg(script)
         .arg(runtime)
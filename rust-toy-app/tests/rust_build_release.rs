@@ -5,14 +5,24 @@ mod common;
 use assert_cmd::prelude::*;
 use common::assert_manpage_exists_in;
 use std::path::PathBuf;
-use std::process::Command;
 
+mod cross_compile;
 mod runtime;
 mod test_helpers;
-use runtime::runtime_available;
-use test_helpers::EnvGuard;
-
-const TARGETS: &[&str] = &["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"];
+mod test_support;
+use test_helpers::EnvMock;
+use test_support::command::create_command;
+
+/// The target matrix this test iterates. Unavailable toolchains/runtimes are
+/// skipped with a precise reason rather than failing the whole job; see
+/// `cross_compile::disabled_reason`.
+const TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-unknown-linux-musl",
+    "aarch64-apple-darwin",
+    "aarch64-apple-ios",
+];
 
 #[test]
 fn builds_release_binary_and_manpage() {
@@ -22,32 +32,30 @@ fn builds_release_binary_and_manpage() {
         .unwrap()
         .join(".github/actions/rust-build-release/src/main.py");
     let action_dir = script.parent().expect("action directory");
-    let _env_guard = EnvGuard::set(
+    let _env_guard = EnvMock::set(
         "GITHUB_ACTION_PATH",
         action_dir.to_str().expect("valid UTF-8 path"),
     );
 
-    for target in TARGETS {
-        if *target != "x86_64-unknown-linux-gnu" {
-            let docker_available = runtime_available("docker");
-            let podman_available = runtime_available("podman");
-            if !docker_available && !podman_available {
-                eprintln!("skipping {} (container runtime required)", target);
-                continue;
-            }
-        }
-
-        Command::new(&script)
-            .arg(target)
+    let report = cross_compile::run_matrix(TARGETS, |target, runner| {
+        let mut cmd = create_command(&script);
+        cmd.arg(target)
             .env("GITHUB_ACTION_PATH", action_dir)
-            .current_dir(&project_dir)
-            .assert()
-            .success();
+            .current_dir(&project_dir);
+        if let Some(runner) = runner {
+            cmd.env(cross_compile::runner_env_var(target), runner);
+        }
+        cmd.assert().success();
 
         assert!(project_dir
             .join(format!("target/{target}/release/rust-toy-app"))
             .exists());
-        let target_root = project_dir.join(format!("target/{target}"));
-        assert_manpage_exists_in(&target_root);
+        assert_manpage_exists_in(&project_dir.join(format!("target/{target}")));
+        Ok(())
+    })
+    .expect("matrix build should not error");
+
+    if !report.skipped().is_empty() {
+        eprintln!("skipped targets: {:?}", report.skipped());
     }
 }
@@ -0,0 +1,208 @@
+//! Declarative test-case runner for rust-toy-app's E2E scenarios.
+//!
+//! Each behaviour ("build a binary", "build and run with args and assert
+//! exit/stdout", "build a library artifact") is described as a row in
+//! [`CASES`] instead of an ad-hoc `#[test]` per behaviour, so new release
+//! formats can plug in by adding a row. A case may also be gated by a
+//! `cfg(...)` expression (evaluated against the host target via
+//! `rust_toy_app::cfg_expr`), and the whole suite can be narrowed to a
+//! subset of cases via the `TEST_CASE_FILTER` env var (a comma-separated
+//! list of substrings matched against case names). The runner gives each
+//! case its own working directory and aggregates failures so one broken
+//! case doesn't mask the rest.
+
+mod cross_compile;
+mod runtime;
+mod test_support;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use rust_toy_app::cfg_expr::{CfgExpr, TargetCfg};
+use test_support::command::create_command;
+
+enum TestKind {
+    /// `cargo build` should succeed.
+    BuildBinary,
+    /// Build, then run with `args` and assert the exit code and stdout.
+    RunAndAssert {
+        args: &'static [&'static str],
+        expected_exit: i32,
+        expected_stdout: &'static str,
+    },
+    /// `cargo build --lib` should succeed.
+    BuildLibrary,
+}
+
+struct TestCase {
+    /// Doubles as the key matched against `TEST_CASE_FILTER`.
+    name: &'static str,
+    /// Only run this case when the expression evaluates true for the host
+    /// target triple.
+    cfg_gate: Option<&'static str>,
+    /// Directory (relative to the crate root) to run the case in.
+    work_dir: Option<&'static str>,
+    kind: TestKind,
+}
+
+const CASES: &[TestCase] = &[
+    TestCase {
+        name: "build_binary",
+        cfg_gate: None,
+        work_dir: None,
+        kind: TestKind::BuildBinary,
+    },
+    TestCase {
+        name: "run_default_greeting",
+        cfg_gate: None,
+        work_dir: None,
+        kind: TestKind::RunAndAssert {
+            args: &[],
+            expected_exit: 0,
+            expected_stdout: "Hello, world!\n",
+        },
+    },
+    TestCase {
+        name: "run_named_greeting",
+        cfg_gate: None,
+        work_dir: None,
+        kind: TestKind::RunAndAssert {
+            args: &["--name", "Ada"],
+            expected_exit: 0,
+            expected_stdout: "Hello, Ada!\n",
+        },
+    },
+    TestCase {
+        name: "build_library",
+        cfg_gate: None,
+        work_dir: None,
+        kind: TestKind::BuildLibrary,
+    },
+    TestCase {
+        name: "run_on_unix_only",
+        cfg_gate: Some("unix"),
+        work_dir: None,
+        kind: TestKind::RunAndAssert {
+            args: &[],
+            expected_exit: 0,
+            expected_stdout: "Hello, world!\n",
+        },
+    },
+];
+
+#[test]
+fn runs_declarative_test_case_matrix() {
+    let project_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut failures = Vec::new();
+
+    for case in CASES {
+        if !filter_allows(case.name) {
+            eprintln!("skipping {}: excluded by TEST_CASE_FILTER", case.name);
+            continue;
+        }
+
+        match case_enabled(case) {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!(
+                    "skipping {}: cfg gate {:?} not satisfied on this host",
+                    case.name, case.cfg_gate
+                );
+                continue;
+            }
+            Err(err) => {
+                failures.push(format!("{}: {err}", case.name));
+                continue;
+            }
+        }
+
+        let work_dir = match &case.work_dir {
+            Some(dir) => project_dir.join(dir),
+            None => project_dir.clone(),
+        };
+        if let Err(err) = run_case(case, &work_dir) {
+            failures.push(format!("{}: {err}", case.name));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "test case matrix had failures:\n{}",
+        failures.join("\n")
+    );
+}
+
+fn filter_allows(name: &str) -> bool {
+    match std::env::var("TEST_CASE_FILTER") {
+        Ok(filter) if !filter.trim().is_empty() => {
+            filter.split(',').any(|key| name.contains(key.trim()))
+        }
+        _ => true,
+    }
+}
+
+fn case_enabled(case: &TestCase) -> Result<bool, String> {
+    let Some(gate) = case.cfg_gate else {
+        return Ok(true);
+    };
+    let expr = CfgExpr::parse(gate).map_err(|err| format!("invalid cfg gate {gate:?}: {err}"))?;
+    Ok(expr.evaluate(&TargetCfg::for_triple(cross_compile::host_triple())))
+}
+
+fn run_case(case: &TestCase, work_dir: &Path) -> Result<(), String> {
+    match &case.kind {
+        TestKind::BuildBinary => cargo_build(work_dir, &[]),
+        TestKind::RunAndAssert {
+            args,
+            expected_exit,
+            expected_stdout,
+        } => {
+            cargo_build(work_dir, &[])?;
+            run_and_assert(work_dir, args, *expected_exit, expected_stdout)
+        }
+        TestKind::BuildLibrary => cargo_build(work_dir, &["--lib"]),
+    }
+}
+
+fn cargo_build(work_dir: &Path, extra_args: &[&str]) -> Result<(), String> {
+    let status = create_command("cargo")
+        .arg("build")
+        .args(extra_args)
+        .current_dir(work_dir)
+        .status()
+        .map_err(|err| format!("failed to run cargo build: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("cargo build {extra_args:?} failed with {status}"))
+    }
+}
+
+fn run_and_assert(
+    work_dir: &Path,
+    args: &[&str],
+    expected_exit: i32,
+    expected_stdout: &str,
+) -> Result<(), String> {
+    let mut cmd = Command::cargo_bin("rust-toy-app").map_err(|err| err.to_string())?;
+    cmd.args(args).current_dir(work_dir);
+    let output = cmd.output().map_err(|err| err.to_string())?;
+
+    let actual_exit = output.status.code().unwrap_or(-1);
+    if actual_exit != expected_exit {
+        return Err(format!(
+            "expected exit code {expected_exit}, got {actual_exit} (stderr: {})",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let actual_stdout = String::from_utf8_lossy(&output.stdout);
+    if actual_stdout != expected_stdout {
+        return Err(format!(
+            "expected stdout {expected_stdout:?}, got {actual_stdout:?}"
+        ));
+    }
+
+    Ok(())
+}
@@ -1,9 +1,14 @@
 //! Test utilities for thread-safe environment variable mutations.
 //!
-//! This module provides `EnvGuard`, an RAII helper that serialises environment
-//! variable changes during tests using a global mutex and restores previous
-//! values when the guard is dropped.
+//! This module provides `EnvMock`, an RAII helper that serialises environment
+//! variable changes during tests using a global mutex and restores the
+//! previous state when the guard is dropped. Unlike a single-variable guard,
+//! it can set several keys and unset others under one lock acquisition, so
+//! tests that need a whole scenario's worth of variables (e.g.
+//! `GITHUB_ACTION_PATH` plus target overrides and CI flags) don't need to
+//! nest guards or fight the mutex for ordering.
 
+use std::collections::BTreeMap;
 use std::env;
 use std::sync::{Mutex, MutexGuard, OnceLock};
 
@@ -12,36 +17,176 @@ fn env_mutex() -> &'static Mutex<()> {
     LOCK.get_or_init(|| Mutex::new(()))
 }
 
-pub struct EnvGuard {
-    key: String,
-    previous: Option<String>,
-    lock_guard: Option<MutexGuard<'static, ()>>,
+/// The state of a variable before `EnvMock` touched it, so it can be restored
+/// precisely (including the "was unset" case) on drop.
+enum Previous {
+    Set(String),
+    Unset,
 }
 
-impl EnvGuard {
-    pub fn set(key: &str, value: &str) -> Self {
+fn capture(key: &str) -> Previous {
+    env::var(key).map_or(Previous::Unset, Previous::Set)
+}
+
+/// Builder that queues a batch of environment mutations before applying them
+/// under a single lock acquisition.
+#[derive(Default)]
+pub struct EnvMockBuilder {
+    sets: BTreeMap<String, String>,
+    unsets: Vec<String>,
+}
+
+impl EnvMockBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `key` to be set to `value`.
+    #[must_use]
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.sets.insert(key.into(), value.into());
+        self
+    }
+
+    /// Queue `key` to be removed from the environment.
+    #[must_use]
+    pub fn unset(mut self, key: impl Into<String>) -> Self {
+        self.unsets.push(key.into());
+        self
+    }
+
+    /// Apply the queued mutations, returning a guard that restores the
+    /// previous environment when dropped.
+    #[must_use]
+    pub fn apply(self) -> EnvMock {
         let lock_guard = env_mutex().lock().unwrap();
-        let previous = env::var(key).ok();
+        let mut previous = BTreeMap::new();
 
-        // SAFETY: Access is serialized by the mutex, preventing concurrent
-        // mutations of the process environment during this guard's lifetime.
-        unsafe { env::set_var(key, value) };
+        for key in &self.unsets {
+            previous.entry(key.clone()).or_insert_with(|| capture(key));
+            // SAFETY: Access is serialized by `env_mutex`, preventing
+            // concurrent mutations of the process environment.
+            unsafe { env::remove_var(key) };
+        }
+        for (key, value) in &self.sets {
+            previous.entry(key.clone()).or_insert_with(|| capture(key));
+            // SAFETY: Access is serialized by `env_mutex`, preventing
+            // concurrent mutations of the process environment.
+            unsafe { env::set_var(key, value) };
+        }
 
-        Self {
-            key: key.to_owned(),
+        EnvMock {
             previous,
-            lock_guard: Some(lock_guard),
+            _lock_guard: lock_guard,
         }
     }
+
+    /// Apply the queued mutations, run `body`, and restore the environment
+    /// afterwards even if `body` panics.
+    pub fn scoped<R>(self, body: impl FnOnce() -> R) -> R {
+        let _guard = self.apply();
+        body()
+    }
+}
+
+/// RAII guard produced by [`EnvMockBuilder::apply`]. Restores every mutated
+/// variable to its prior value (or removes it, if it was previously unset)
+/// when dropped.
+pub struct EnvMock {
+    previous: BTreeMap<String, Previous>,
+    _lock_guard: MutexGuard<'static, ()>,
 }
 
-impl Drop for EnvGuard {
+impl EnvMock {
+    /// Start building a scoped set of environment mutations.
+    #[must_use]
+    pub fn builder() -> EnvMockBuilder {
+        EnvMockBuilder::new()
+    }
+
+    /// Set a single variable immediately. Equivalent to
+    /// `EnvMock::builder().set(key, value).apply()`.
+    #[must_use]
+    pub fn set(key: &str, value: &str) -> Self {
+        Self::builder().set(key, value).apply()
+    }
+
+    /// Run `body` with `builder`'s mutations applied, guaranteeing
+    /// restoration even if `body` panics.
+    pub fn scoped<R>(builder: EnvMockBuilder, body: impl FnOnce() -> R) -> R {
+        builder.scoped(body)
+    }
+}
+
+impl Drop for EnvMock {
     fn drop(&mut self) {
-        match self.previous.as_ref() {
-            Some(previous) => unsafe { env::set_var(&self.key, previous) },
-            None => unsafe { env::remove_var(&self.key) },
+        for (key, previous) in &self.previous {
+            match previous {
+                // SAFETY: Access is serialized by `env_mutex`, preventing
+                // concurrent mutations of the process environment.
+                Previous::Set(value) => unsafe { env::set_var(key, value) },
+                Previous::Unset => unsafe { env::remove_var(key) },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn sets_and_restores_multiple_keys() {
+        let key_a = "ENV_MOCK_TEST_A";
+        let key_b = "ENV_MOCK_TEST_B";
+        // SAFETY: single-threaded test process at this point.
+        unsafe { env::set_var(key_a, "original") };
+        // SAFETY: single-threaded test process at this point.
+        unsafe { env::remove_var(key_b) };
+
+        {
+            let _guard = EnvMock::builder()
+                .set(key_a, "mocked")
+                .set(key_b, "also-mocked")
+                .apply();
+            assert_eq!(env::var(key_a).unwrap(), "mocked");
+            assert_eq!(env::var(key_b).unwrap(), "also-mocked");
         }
-        // Release the mutex guard after restoring the environment variable.
-        self.lock_guard.take();
+
+        assert_eq!(env::var(key_a).unwrap(), "original");
+        assert!(env::var(key_b).is_err());
+    }
+
+    #[test]
+    fn unset_removes_and_restores() {
+        let key = "ENV_MOCK_TEST_UNSET";
+        // SAFETY: single-threaded test process at this point.
+        unsafe { env::set_var(key, "present") };
+
+        {
+            let _guard = EnvMock::builder().unset(key).apply();
+            assert!(env::var(key).is_err());
+        }
+
+        assert_eq!(env::var(key).unwrap(), "present");
+    }
+
+    #[test]
+    fn scoped_restores_even_on_panic() {
+        let key = "ENV_MOCK_TEST_SCOPED_PANIC";
+        // SAFETY: single-threaded test process at this point.
+        unsafe { env::remove_var(key) };
+
+        let result = panic::catch_unwind(|| {
+            EnvMock::scoped(EnvMockBuilder::new().set(key, "mocked"), || {
+                assert_eq!(env::var(key).unwrap(), "mocked");
+                panic!("boom");
+            });
+        });
+
+        assert!(result.is_err());
+        assert!(env::var(key).is_err());
     }
 }
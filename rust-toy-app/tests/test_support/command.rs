@@ -0,0 +1,166 @@
+//! Cross-platform helpers for building `std::process::Command`s from test
+//! code: resolving the executable via `PATH` before Windows can be tricked
+//! into running a same-named binary planted in the current working
+//! directory, and parsing quoted argument strings the way a shell would
+//! instead of naively splitting on whitespace.
+
+use std::ffi::OsStr;
+use std::process::Command;
+
+/// Build a `Command` for `program`.
+///
+/// On Windows, `program` is pre-resolved to an absolute path via a `PATH`
+/// search, so a malicious `docker.exe`/`podman.exe` placed in the current
+/// working directory cannot be executed instead of the real one. On other
+/// platforms `Command::new` already searches `PATH` without consulting the
+/// current directory, so the program name is used as-is.
+#[must_use]
+#[allow(clippy::disallowed_methods)] // This is the one sanctioned call site.
+pub fn create_command(program: impl AsRef<OsStr>) -> Command {
+    let program = program.as_ref();
+    #[cfg(windows)]
+    {
+        Command::new(windows_path::resolve(program).unwrap_or_else(|| program.to_os_string()))
+    }
+    #[cfg(not(windows))]
+    {
+        Command::new(program)
+    }
+}
+
+#[cfg(windows)]
+mod windows_path {
+    use std::env;
+    use std::ffi::{OsStr, OsString};
+    use std::path::Path;
+
+    /// Search `PATH` (and `PATHEXT`) for `program`, returning its resolved
+    /// absolute path. Returns `None` for anything that already contains a
+    /// path separator, since those are not subject to the cwd-hijack risk
+    /// this guards against.
+    pub(super) fn resolve(program: &OsStr) -> Option<OsString> {
+        let candidate_path = Path::new(program);
+        if candidate_path.components().count() > 1 {
+            return None;
+        }
+
+        let path_var = env::var_os("PATH")?;
+        let extensions: Vec<String> = env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(str::to_owned)
+            .collect();
+
+        for dir in env::split_paths(&path_var) {
+            let base = dir.join(candidate_path);
+            if base.is_file() {
+                return Some(base.into_os_string());
+            }
+            for ext in &extensions {
+                let mut with_ext = base.clone().into_os_string();
+                with_ext.push(ext);
+                if Path::new(&with_ext).is_file() {
+                    return Some(with_ext);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Split `input` into argv entries, honouring single and double quotes and
+/// backslash escapes the way a POSIX shell would, instead of naively
+/// splitting on whitespace (which mangles quoted arguments like
+/// `--name "Jane Doe"`).
+#[must_use]
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut has_current = false;
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if !in_double => {
+                in_single = !in_single;
+                has_current = true;
+            }
+            '"' if !in_single => {
+                in_double = !in_double;
+                has_current = true;
+            }
+            '\\' if !in_single => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                } else {
+                    current.push('\\');
+                }
+                has_current = true;
+            }
+            c if c.is_whitespace() && !in_single && !in_double => {
+                if has_current {
+                    args.push(std::mem::take(&mut current));
+                    has_current = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_current = true;
+            }
+        }
+    }
+    if has_current {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_plain_whitespace() {
+        assert_eq!(split_args("--name Bob"), vec!["--name", "Bob"]);
+    }
+
+    #[test]
+    fn keeps_double_quoted_argument_together() {
+        assert_eq!(
+            split_args(r#"--name "Jane Doe""#),
+            vec!["--name", "Jane Doe"]
+        );
+    }
+
+    #[test]
+    fn keeps_single_quoted_argument_together() {
+        assert_eq!(split_args("--name 'Jane Doe'"), vec!["--name", "Jane Doe"]);
+    }
+
+    #[test]
+    fn honours_backslash_escapes() {
+        assert_eq!(split_args(r#"--name Jane\ Doe"#), vec!["--name", "Jane Doe"]);
+    }
+
+    #[test]
+    fn collapses_repeated_whitespace() {
+        assert_eq!(split_args("  --name   Bob  "), vec!["--name", "Bob"]);
+    }
+
+    #[test]
+    fn empty_input_yields_no_args() {
+        assert!(split_args("").is_empty());
+    }
+
+    #[test]
+    fn create_command_runs_a_simple_program() {
+        let mut cmd = create_command(if cfg!(windows) { "cmd" } else { "true" });
+        if cfg!(windows) {
+            cmd.arg("/C").arg("exit 0");
+        }
+        let status = cmd.status().expect("command should run");
+        assert!(status.success());
+    }
+}
@@ -0,0 +1,4 @@
+//! Shared test-support utilities used across the rust-toy-app integration and
+//! BDD test binaries.
+
+pub mod command;